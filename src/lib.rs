@@ -1,6 +1,8 @@
 // #![deny(warnings)]
+mod hdr_telemetry;
 mod telemetry;
 mod telemetry_layer;
+mod trace_ctx_handle;
 mod visitor;
 
 #[cfg(test)]
@@ -8,5 +10,7 @@ mod visitor;
 #[cfg(test)]
 extern crate lazy_static;
 
-pub use crate::telemetry::{SpanId, TraceCtx, TraceId};
-pub use crate::telemetry_layer::TelemetryLayer;
\ No newline at end of file
+pub use crate::hdr_telemetry::HdrHistogramTelemetry;
+pub use crate::telemetry::{BlackholeTelemetry, HoneycombTelemetry, SpanId, Telemetry, TraceCtx, TraceId};
+pub use crate::telemetry_layer::TelemetryLayer;
+pub use crate::trace_ctx_handle::TraceCtxHandle;
\ No newline at end of file
@@ -0,0 +1,304 @@
+//! Core types published by `TelemetryLayer` to a pluggable `Telemetry` backend,
+//! plus trace-context propagation (`TraceCtx`) carried via tracing span
+//! extensions.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use tracing::Id;
+
+/// A process-instance-specific span identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpanId {
+    /// Identifies the process instance that created the span.
+    pub instance_id: u64,
+    /// Identifies the span within its owning process instance.
+    pub tracing_id: Id,
+}
+
+/// Identifies a whole distributed trace, independent of any one process.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceId(String);
+
+impl TraceId {
+    /// Construct a new `TraceId` from an arbitrary string.
+    pub fn new(s: String) -> Self {
+        TraceId(s)
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Trace membership for a span: which trace it's part of, and (if this span
+/// is the local root of the trace) the remote/explicit span it continues
+/// from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceCtx {
+    /// The trace this span belongs to.
+    pub trace_id: TraceId,
+    /// The span (possibly in another process) this span continues from, if
+    /// this span is the local root of the trace.
+    pub parent_span: Option<SpanId>,
+}
+
+impl TraceCtx {
+    /// Record `self` as the trace context of the current span, so that it
+    /// (and any of its descendants, once evaluated) are recognized as part of
+    /// this trace.
+    pub fn record_on_current_span(self) {
+        tracing::dispatcher::get_default(|dispatch| {
+            if let Some(id) = tracing::Span::current().id() {
+                if let Some(layer) = dispatch.downcast_ref::<crate::telemetry_layer::TelemetryLayer>() {
+                    layer.record_trace_ctx(self.clone(), id);
+                }
+            }
+        });
+    }
+
+    /// Evaluate the trace context of the current span, if any.
+    pub fn eval_current_trace_ctx() -> Option<TraceCtx> {
+        tracing::dispatcher::get_default(|dispatch| {
+            use tracing_subscriber::registry::LookupSpan;
+
+            let id = tracing::Span::current().id()?;
+            let registry = dispatch.downcast_ref::<tracing_subscriber::Registry>()?;
+            let layer = dispatch.downcast_ref::<crate::telemetry_layer::TelemetryLayer>()?;
+
+            let iter = itertools::unfold(Some(id), |st| match st.take() {
+                Some(id) => {
+                    let span = registry.span(&id)?;
+                    *st = span.parent().map(|parent| parent.id());
+                    Some(span)
+                }
+                None => None,
+            });
+
+            layer.eval_ctx(iter)
+        })
+    }
+
+    /// Add a causal reference from the current span to `(trace_id, span_id)`,
+    /// e.g. linking a fan-in/batch-consumer span to a producer span that fed
+    /// it, in addition to its single `parent_span`. Reported as `trace.links`
+    /// when the current span closes.
+    pub fn add_link(trace_id: TraceId, span_id: SpanId) {
+        tracing::dispatcher::get_default(|dispatch| {
+            if let Some(id) = tracing::Span::current().id() {
+                if let Some(layer) = dispatch.downcast_ref::<crate::telemetry_layer::TelemetryLayer>() {
+                    layer.add_link(id, trace_id, span_id);
+                }
+            }
+        });
+    }
+}
+
+/// A span as reported to a `Telemetry` backend.
+#[derive(Debug)]
+pub struct Span<'a> {
+    /// This span's id.
+    pub id: SpanId,
+    /// The module/crate path the span was created in.
+    pub target: &'static str,
+    /// The span's verbosity level.
+    pub level: tracing::Level,
+    /// The span's parent, if any.
+    pub parent_id: Option<SpanId>,
+    /// The name of the span's parent, if any.
+    pub parent_name: Option<&'static str>,
+    /// The span's name.
+    pub name: &'static str,
+    /// When the span was created.
+    pub initialized_at: DateTime<Utc>,
+    /// The trace this span belongs to.
+    pub trace_id: TraceId,
+    /// How long the span was open for, in milliseconds.
+    pub elapsed_ms: i64,
+    /// How long the span was open for, in nanoseconds. Captured via
+    /// `Instant`, so unlike `elapsed_ms` it retains sub-millisecond precision.
+    pub elapsed_ns: u64,
+    /// Additional causal references to spans that aren't this span's single
+    /// `parent_id`, e.g. every producer span that fed a batch-consumer span.
+    pub links: Vec<(TraceId, SpanId)>,
+    /// The name of the service reporting this span.
+    pub service_name: &'a str,
+    /// Field values recorded on the span.
+    pub values: HashMap<String, libhoney::Value>,
+}
+
+/// An event as reported to a `Telemetry` backend.
+#[derive(Debug)]
+pub struct Event<'a> {
+    /// The trace this event belongs to.
+    pub trace_id: TraceId,
+    /// The span this event occurred within.
+    pub parent_id: Option<SpanId>,
+    /// The name of the span this event occurred within, if any.
+    pub parent_name: Option<&'static str>,
+    /// When the event occurred.
+    pub initialized_at: DateTime<Utc>,
+    /// The event's verbosity level.
+    pub level: tracing::Level,
+    /// The event's name.
+    pub name: &'static str,
+    /// The module/crate path the event was recorded in.
+    pub target: &'static str,
+    /// Nanoseconds since the previous event recorded on the same span, or
+    /// since the span was created if this is the first. Captured via
+    /// `Instant`, so it carries sub-millisecond precision.
+    pub elapsed_ns: u64,
+    /// The name of the service reporting this event.
+    pub service_name: &'a str,
+    /// Field values recorded on the event.
+    pub values: HashMap<String, libhoney::Value>,
+}
+
+/// A pluggable backend that `TelemetryLayer` publishes spans and events to.
+pub trait Telemetry {
+    /// Report a closed span.
+    fn report_span(&self, span: Span);
+    /// Report an event.
+    fn report_event(&self, event: Event);
+}
+
+/// A `Telemetry` that discards every span and event.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BlackholeTelemetry;
+
+impl Telemetry for BlackholeTelemetry {
+    fn report_span(&self, _span: Span) {}
+    fn report_event(&self, _event: Event) {}
+}
+
+/// Publishes spans and events to honeycomb.io via `libhoney`.
+pub struct HoneycombTelemetry {
+    client: libhoney::Client<libhoney::transmission::Transmission>,
+}
+
+impl fmt::Debug for HoneycombTelemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HoneycombTelemetry").finish()
+    }
+}
+
+impl HoneycombTelemetry {
+    /// Construct a new `HoneycombTelemetry` from the given `libhoney` config.
+    pub fn new(config: libhoney::Config) -> Self {
+        HoneycombTelemetry {
+            client: libhoney::init(config),
+        }
+    }
+
+    fn send(&self, mut data: HashMap<String, libhoney::Value>) {
+        let mut event = self.client.new_event();
+        for (k, v) in data.drain() {
+            event.add_field(&k, v);
+        }
+        let _ = event.send(&self.client);
+    }
+}
+
+impl Telemetry for HoneycombTelemetry {
+    fn report_span(&self, span: Span) {
+        let mut values = span.values;
+        values.insert("trace.trace_id".to_string(), libhoney::json!(span.trace_id.to_string()));
+        values.insert("name".to_string(), libhoney::json!(span.name));
+        values.insert("service_name".to_string(), libhoney::json!(span.service_name));
+        values.insert("duration_ms".to_string(), libhoney::json!(span.elapsed_ms));
+        if let Some(parent_id) = &span.parent_id {
+            values.insert(
+                "trace.parent_id".to_string(),
+                libhoney::json!(format!("{:?}", parent_id)),
+            );
+        }
+        if !span.links.is_empty() {
+            let links: Vec<_> = span
+                .links
+                .iter()
+                .map(|(trace_id, span_id)| {
+                    libhoney::json!({
+                        "trace_id": trace_id.to_string(),
+                        "span_id": format!("{:?}", span_id),
+                    })
+                })
+                .collect();
+            values.insert("trace.links".to_string(), libhoney::json!(links));
+        }
+        self.send(values);
+    }
+
+    fn report_event(&self, event: Event) {
+        let mut values = event.values;
+        values.insert("trace.trace_id".to_string(), libhoney::json!(event.trace_id.to_string()));
+        values.insert("name".to_string(), libhoney::json!(event.name));
+        values.insert("service_name".to_string(), libhoney::json!(event.service_name));
+        if let Some(parent_id) = &event.parent_id {
+            values.insert(
+                "trace.parent_id".to_string(),
+                libhoney::json!(format!("{:?}", parent_id)),
+            );
+        }
+        self.send(values);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct TestSpan {
+        pub(crate) id: SpanId,
+        pub(crate) parent_id: Option<SpanId>,
+        pub(crate) trace_id: TraceId,
+        pub(crate) links: Vec<(TraceId, SpanId)>,
+        pub(crate) values: HashMap<String, libhoney::Value>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct TestEvent {
+        pub(crate) parent_id: Option<SpanId>,
+        pub(crate) trace_id: TraceId,
+        pub(crate) values: HashMap<String, libhoney::Value>,
+    }
+
+    /// A `Telemetry` that records every reported span/event into shared
+    /// buffers, for use in tests.
+    pub(crate) struct TestTelemetry {
+        spans: Arc<Mutex<Vec<TestSpan>>>,
+        events: Arc<Mutex<Vec<TestEvent>>>,
+    }
+
+    impl TestTelemetry {
+        pub(crate) fn new(
+            spans: Arc<Mutex<Vec<TestSpan>>>,
+            events: Arc<Mutex<Vec<TestEvent>>>,
+        ) -> Self {
+            TestTelemetry { spans, events }
+        }
+    }
+
+    impl Telemetry for TestTelemetry {
+        fn report_span(&self, span: Span) {
+            self.spans.lock().unwrap().push(TestSpan {
+                id: span.id,
+                parent_id: span.parent_id,
+                trace_id: span.trace_id,
+                links: span.links,
+                values: span.values,
+            });
+        }
+
+        fn report_event(&self, event: Event) {
+            self.events.lock().unwrap().push(TestEvent {
+                parent_id: event.parent_id,
+                trace_id: event.trace_id,
+                values: event.values,
+            });
+        }
+    }
+}
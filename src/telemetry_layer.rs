@@ -1,4 +1,4 @@
-use crate::telemetry::{self, BlackholeTelemetry, HoneycombTelemetry, SpanId, Telemetry, TraceCtx};
+use crate::telemetry::{self, BlackholeTelemetry, HoneycombTelemetry, SpanId, Telemetry, TraceCtx, TraceId};
 use crate::visitor::HoneycombVisitor;
 use chrono::{DateTime, Utc};
 use rand::Rng;
@@ -16,6 +16,9 @@ pub struct TelemetryLayer {
     pub(crate) instance_id: u64,
     // lazy trace ctx + init time
     span_data: RwLock<HashMap<Id, TraceCtx>>,
+    // additional causal references recorded on a span via `TraceCtx::add_link`,
+    // reported as `trace.links` when the span closes
+    links: RwLock<HashMap<Id, Vec<(TraceId, SpanId)>>>,
 }
 
 impl TelemetryLayer {
@@ -32,18 +35,29 @@ impl TelemetryLayer {
         Self::new_("".to_string(), telemetry)
     }
 
+    /// Create a new `TelemetryLayer` that publishes to an arbitrary `Telemetry`
+    /// backend, e.g. a `HdrHistogramTelemetry` wrapping a `HoneycombTelemetry`.
+    pub fn new_with_telemetry(
+        service_name: String,
+        telemetry: Box<dyn Telemetry + Send + Sync + 'static>,
+    ) -> Self {
+        Self::new_(service_name, telemetry)
+    }
+
     pub(crate) fn new_(
         service_name: String,
         telemetry: Box<dyn Telemetry + Send + Sync + 'static>,
     ) -> Self {
         let instance_id = rand::thread_rng().gen();
         let span_data = RwLock::new(HashMap::new());
+        let links = RwLock::new(HashMap::new());
 
         TelemetryLayer {
             instance_id,
             service_name,
             telemetry,
             span_data,
+            links,
         }
     }
 
@@ -52,6 +66,11 @@ impl TelemetryLayer {
         span_data.insert(id, trace_ctx); // TODO: handle overwrite?
     }
 
+    pub(crate) fn add_link(&self, id: Id, trace_id: TraceId, span_id: SpanId) {
+        let mut links = self.links.write().expect("write lock!");
+        links.entry(id).or_insert_with(Vec::new).push((trace_id, span_id));
+    }
+
     pub fn eval_ctx<
         'a,
         X: 'a + registry::LookupSpan<'a>,
@@ -170,6 +189,27 @@ where
             None => {} // not part of a trace, don't bother recording via honeycomb
             Some(parent_id) => {
                 let initialized_at = Utc::now();
+                let parent_span = ctx.span(&parent_id).expect("span data not found during on_event");
+                let parent_name = Some(parent_span.metadata().name());
+                let elapsed_ns = {
+                    let mut extensions_mut = parent_span.extensions_mut();
+                    let now = std::time::Instant::now();
+                    match extensions_mut.get_mut::<LastEventAt>() {
+                        Some(LastEventAt(last)) => {
+                            let elapsed = last.elapsed().as_nanos() as u64;
+                            *last = now;
+                            elapsed
+                        }
+                        None => {
+                            let elapsed = extensions_mut
+                                .get::<SpanInitAt>()
+                                .map(|SpanInitAt(_, entered_at)| entered_at.elapsed().as_nanos() as u64)
+                                .unwrap_or(0);
+                            extensions_mut.insert(LastEventAt(now));
+                            elapsed
+                        }
+                    }
+                };
 
                 let mut visitor = HoneycombVisitor(HashMap::new());
                 event.record(&mut visitor);
@@ -191,10 +231,12 @@ where
                     let event = telemetry::Event {
                         trace_id: parent_trace_ctx.trace_id,
                         parent_id: Some(self.span_id(parent_id.clone())),
+                        parent_name,
                         initialized_at,
                         level: event.metadata().level().clone(),
                         name: event.metadata().name(),
                         target: event.metadata().target(),
+                        elapsed_ns,
                         service_name: &self.service_name,
                         values: visitor.0,
                     };
@@ -220,19 +262,28 @@ where
             None => None,
         });
 
+        // drop any links recorded on this span regardless of whether it turns
+        // out to be part of a trace, since `tracing-subscriber` recycles `Id`s
+        // and a leftover entry would otherwise attach to a later, unrelated
+        // span that reuses the same id
+        let links = self.links.write().expect("write lock!").remove(&id).unwrap_or_default();
+
         // if span's enclosing ctx has a trace id, eval & use to report telemetry
         if let Some(trace_ctx) = self.eval_ctx(iter) {
             let mut extensions_mut = span.extensions_mut();
             let visitor: HoneycombVisitor = extensions_mut
                 .remove()
                 .expect("should be present on all spans");
-            let SpanInitAt(initialized_at) = extensions_mut
+            let SpanInitAt(initialized_at, entered_at) = extensions_mut
                 .remove()
                 .expect("should be present on all spans");
 
             let now = Utc::now();
             let now = now.timestamp_millis();
             let elapsed_ms = now - initialized_at.timestamp_millis();
+            let elapsed_ns = entered_at.elapsed().as_nanos() as u64;
+
+            let parent_name = span.parents().next().map(|parent| parent.metadata().name());
 
             let parent_id = match trace_ctx.parent_span {
                 None => span
@@ -247,10 +298,13 @@ where
                 target: span.metadata().target(),
                 level: span.metadata().level().clone(), // copy on inner type
                 parent_id,
+                parent_name,
                 name: span.metadata().name(),
                 initialized_at: initialized_at.clone(),
                 trace_id: trace_ctx.trace_id,
                 elapsed_ms,
+                elapsed_ns,
+                links,
                 service_name: &self.service_name,
                 values: visitor.0,
             };
@@ -266,16 +320,20 @@ where
 
 struct LazyTraceCtx(TraceCtx);
 
-struct SpanInitAt(DateTime<Utc>);
+struct SpanInitAt(DateTime<Utc>, std::time::Instant);
 
 impl SpanInitAt {
     fn new() -> Self {
         let initialized_at = Utc::now();
 
-        Self(initialized_at)
+        Self(initialized_at, std::time::Instant::now())
     }
 }
 
+/// When the most recent event was recorded on a span, so the next one can
+/// report its elapsed time relative to it rather than to the span's start.
+struct LastEventAt(std::time::Instant);
+
 #[derive(Debug)]
 struct PathToRoot<'a, S> {
     registry: &'a S,
@@ -349,6 +407,74 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_span_links() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let cap = crate::telemetry::test::TestTelemetry::new(spans.clone(), events.clone());
+        let layer = TelemetryLayer::new_("test_svc_name".to_string(), Box::new(cap));
+        let subscriber = layer.with_subscriber(registry::Registry::default());
+
+        let linked_trace_id = TraceId::new("linked-trace-id".to_string());
+        let linked_span_id = SpanId {
+            tracing_id: Id::from_u64(9999),
+            instance_id: 1,
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            #[instrument]
+            fn f(linked_trace_id: TraceId, linked_span_id: SpanId) {
+                explicit_trace_ctx().record_on_current_span();
+                TraceCtx::add_link(linked_trace_id, linked_span_id);
+            }
+
+            f(linked_trace_id.clone(), linked_span_id.clone());
+        });
+
+        let spans = spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].links, vec![(linked_trace_id, linked_span_id)]);
+    }
+
+    #[test]
+    fn test_links_do_not_leak_onto_a_later_span_reusing_the_same_id() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let cap = crate::telemetry::test::TestTelemetry::new(spans.clone(), events.clone());
+        let layer = TelemetryLayer::new_("test_svc_name".to_string(), Box::new(cap));
+        let subscriber = layer.with_subscriber(registry::Registry::default());
+
+        let linked_trace_id = TraceId::new("linked-trace-id".to_string());
+        let linked_span_id = SpanId {
+            tracing_id: Id::from_u64(9999),
+            instance_id: 1,
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            #[instrument]
+            fn untraced(linked_trace_id: TraceId, linked_span_id: SpanId) {
+                // never resolves a trace context, so this span isn't reported
+                // at all, but it still has a link recorded against its id
+                TraceCtx::add_link(linked_trace_id, linked_span_id);
+            }
+
+            #[instrument]
+            fn traced() {
+                explicit_trace_ctx().record_on_current_span();
+            }
+
+            untraced(linked_trace_id, linked_span_id);
+            traced();
+        });
+
+        // `untraced`'s id is freed once it closes, and `tracing-subscriber`'s
+        // registry reuses ids, so `traced` is likely to get it back; its
+        // reported span must not inherit `untraced`'s stale link.
+        let spans = spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].links.is_empty());
+    }
+
     // run async fn (with multiple entry and exit for each span due to delay) with test scenario
     #[test]
     fn test_async_instrument() {
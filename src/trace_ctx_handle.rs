@@ -0,0 +1,113 @@
+//! Explicit cross-thread/async trace-context capture and continuation.
+//!
+//! `TraceCtx::record_on_current_span`/`eval_current_trace_ctx` resolve
+//! through the current span, so a trace context set on one task is invisible
+//! to e.g. a `tokio::spawn`ed task running on another thread. A
+//! `TraceCtxHandle` captures a trace context explicitly so it can be moved
+//! across that boundary, then re-attached to the new span tree.
+
+use crate::telemetry::{SpanId, TraceCtx, TraceId};
+
+/// A trace context captured explicitly (rather than resolved from the current
+/// span implicitly), so it can be moved into another thread or future and
+/// re-attached there via `continue_in_current_span`.
+#[derive(Debug, Clone)]
+pub struct TraceCtxHandle {
+    trace_id: TraceId,
+    span_id: SpanId,
+}
+
+impl TraceCtxHandle {
+    /// Capture the trace context and `SpanId` of the current span, if it's
+    /// part of a trace.
+    pub fn capture() -> Option<Self> {
+        tracing::dispatcher::get_default(|dispatch| {
+            let id = tracing::Span::current().id()?;
+            let layer = dispatch.downcast_ref::<crate::telemetry_layer::TelemetryLayer>()?;
+            let trace_ctx = TraceCtx::eval_current_trace_ctx()?;
+
+            Some(TraceCtxHandle {
+                trace_id: trace_ctx.trace_id,
+                span_id: SpanId {
+                    instance_id: layer.instance_id,
+                    tracing_id: id,
+                },
+            })
+        })
+    }
+
+    /// Re-seed the trace context of the current span so it (as the root of a
+    /// new span tree, e.g. one just entered on a spawned task) continues this
+    /// handle's trace, with the captured span as its parent — a cross-thread
+    /// reference edge rather than a broken root.
+    pub fn continue_in_current_span(&self) {
+        tracing::dispatcher::get_default(|dispatch| {
+            if let Some(id) = tracing::Span::current().id() {
+                if let Some(layer) = dispatch.downcast_ref::<crate::telemetry_layer::TelemetryLayer>() {
+                    layer.record_trace_ctx(
+                        TraceCtx {
+                            trace_id: self.trace_id.clone(),
+                            parent_span: Some(self.span_id.clone()),
+                        },
+                        id,
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::test::TestTelemetry;
+    use crate::TelemetryLayer;
+    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
+    use tracing::instrument;
+    use tracing_subscriber::{layer::Layer, registry};
+
+    #[test]
+    fn continue_in_current_span_links_new_root_to_captured_span() {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let cap = TestTelemetry::new(spans.clone(), events.clone());
+        let layer = TelemetryLayer::new_("test_svc_name".to_string(), Box::new(cap));
+        let subscriber = layer.with_subscriber(registry::Registry::default());
+
+        let captured: RefCell<Option<TraceCtxHandle>> = RefCell::new(None);
+
+        tracing::subscriber::with_default(subscriber, || {
+            #[instrument]
+            fn producer() -> TraceCtxHandle {
+                TraceCtx {
+                    trace_id: TraceId::new("trace-x".to_string()),
+                    parent_span: None,
+                }
+                .record_on_current_span();
+
+                TraceCtxHandle::capture().expect("trace ctx was just recorded")
+            }
+
+            #[instrument]
+            fn consumer(handle: &TraceCtxHandle) {
+                handle.continue_in_current_span();
+            }
+
+            *captured.borrow_mut() = Some(producer());
+            consumer(&captured.borrow().as_ref().unwrap().clone());
+        });
+
+        let spans = spans.lock().unwrap();
+        assert_eq!(spans.len(), 2);
+
+        let producer_span = &spans[0];
+        let consumer_span = &spans[1];
+
+        assert_eq!(producer_span.parent_id, None);
+        assert_eq!(producer_span.trace_id, TraceId::new("trace-x".to_string()));
+
+        assert_eq!(consumer_span.parent_id, Some(producer_span.id.clone()));
+        assert_eq!(consumer_span.trace_id, TraceId::new("trace-x".to_string()));
+    }
+}
@@ -0,0 +1,106 @@
+//! Honeycomb-specific identifiers and telemetry backend.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// A honeycomb.io-specific trace id.
+///
+/// Wraps an opaque string, since honeycomb trace ids are themselves just strings;
+/// callers are free to mint their own (e.g. from a UUID) or recover one via a
+/// `Propagator`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceId(String);
+
+impl TraceId {
+    /// Construct a new `TraceId` from an arbitrary string.
+    pub fn new(s: String) -> Self {
+        TraceId(s)
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A honeycomb.io-specific span id, unique within a single process instance.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SpanId {
+    /// Identifies the process instance that created the span.
+    pub instance_id: u64,
+    /// Identifies the span within its owning process instance.
+    pub tracing_id: u64,
+}
+
+/// Arbitrary key/value data attached to a reported span or event.
+pub type Data = HashMap<String, libhoney::Value>;
+
+/// Decides whether a given span/event should be reported to honeycomb.
+pub trait Sampler: Send + Sync {
+    /// Return `true` if the span/event emitted by `target` at `level`, belonging to
+    /// `trace_id`, should be kept.
+    fn sample(&self, target: &str, level: &tracing::Level, trace_id: &TraceId) -> bool;
+}
+
+/// A `Sampler` that keeps every trace.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AlwaysSampler;
+
+impl Sampler for AlwaysSampler {
+    fn sample(&self, _target: &str, _level: &tracing::Level, _trace_id: &TraceId) -> bool {
+        true
+    }
+}
+
+/// Publishes spans and events to honeycomb.io via `libhoney`.
+pub struct HoneycombTelemetry {
+    client: libhoney::Client<libhoney::transmission::Transmission>,
+    sampler: Box<dyn Sampler>,
+}
+
+impl fmt::Debug for HoneycombTelemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HoneycombTelemetry").finish()
+    }
+}
+
+impl HoneycombTelemetry {
+    /// Construct a new `HoneycombTelemetry` from the given `libhoney` config.
+    ///
+    /// Samples every trace by default; call `set_sampler` to change that.
+    pub fn new(config: libhoney::Config) -> Self {
+        HoneycombTelemetry {
+            client: libhoney::init(config),
+            sampler: Box::new(AlwaysSampler),
+        }
+    }
+
+    /// Replace the sampler used to decide which traces get reported.
+    pub fn set_sampler(&mut self, sampler: Box<dyn Sampler>) {
+        self.sampler = sampler;
+    }
+
+    /// Publish a span's `data` to honeycomb, first consulting the configured
+    /// `Sampler`. Does nothing if the sampler drops `trace_id`.
+    pub fn report_span(&self, target: &str, level: &tracing::Level, trace_id: &TraceId, data: Data) {
+        self.report(target, level, trace_id, data);
+    }
+
+    /// Publish an event's `data` to honeycomb, first consulting the configured
+    /// `Sampler`. Does nothing if the sampler drops `trace_id`.
+    pub fn report_event(&self, target: &str, level: &tracing::Level, trace_id: &TraceId, data: Data) {
+        self.report(target, level, trace_id, data);
+    }
+
+    fn report(&self, target: &str, level: &tracing::Level, trace_id: &TraceId, data: Data) {
+        if !self.sampler.sample(target, level, trace_id) {
+            return;
+        }
+        let mut event = self.client.new_event();
+        for (k, v) in data {
+            event.add_field(&k, v);
+        }
+        let _ = event.send(&self.client);
+    }
+}
@@ -12,9 +12,13 @@
 //! As a tracing layer, `TelemetryLayer` can be composed with other layers to provide stdout logging, filtering, etc.
 
 mod honeycomb;
+mod propagation;
+mod sampler;
 mod visitor;
 
 pub use crate::honeycomb::{HoneycombTelemetry, SpanId, TraceId, Sampler, Data};
+pub use crate::propagation::{B3Propagator, CompositePropagator, Propagator, W3CPropagator, XRayPropagator};
+pub use crate::sampler::DirectiveSampler;
 pub use crate::visitor::HoneycombVisitor;
 use rand::{self, Rng};
 #[doc(no_inline)]
@@ -0,0 +1,157 @@
+use crate::honeycomb::{SpanId, TraceId};
+use crate::propagation::util::{is_all_zero_hex, span_id_from_hex, span_id_to_hex, trace_id_to_hex};
+use crate::propagation::Propagator;
+use std::collections::HashMap;
+
+const B3_SINGLE_HEADER: &str = "b3";
+const B3_TRACE_ID_HEADER: &str = "X-B3-TraceId";
+const B3_SPAN_ID_HEADER: &str = "X-B3-SpanId";
+const B3_SAMPLED_HEADER: &str = "X-B3-Sampled";
+
+/// Propagates trace context using Zipkin's B3 headers. Supports both the
+/// single-header form (`b3: {traceid}-{spanid}-{sampled}`) and the multi-header
+/// form (`X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled`).
+///
+/// Injects both forms simultaneously, and extracts from whichever is present
+/// (preferring the single header).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct B3Propagator;
+
+impl B3Propagator {
+    /// Construct a new `B3Propagator`.
+    pub fn new() -> Self {
+        B3Propagator
+    }
+
+    fn extract_single(carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        let header = carrier.get(B3_SINGLE_HEADER)?;
+        let parts: Vec<&str> = header.split('-').collect();
+        if parts.len() < 2 {
+            return None;
+        }
+        let (trace_id_hex, span_id_hex) = (parts[0], parts[1]);
+        Self::parse(trace_id_hex, span_id_hex)
+    }
+
+    fn extract_multi(carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        let trace_id_hex = carrier.get(B3_TRACE_ID_HEADER)?;
+        let span_id_hex = carrier.get(B3_SPAN_ID_HEADER).map(String::as_str).unwrap_or("");
+        Self::parse(trace_id_hex, span_id_hex)
+    }
+
+    fn parse(trace_id_hex: &str, span_id_hex: &str) -> Option<(TraceId, Option<SpanId>)> {
+        if trace_id_hex.is_empty() || !trace_id_hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        if is_all_zero_hex(trace_id_hex) {
+            return None;
+        }
+        // an absent span id means "trace id only", but a present-and-malformed
+        // one means the header itself is malformed, not parentless
+        let span_id = if span_id_hex.is_empty() {
+            None
+        } else {
+            Some(span_id_from_hex(span_id_hex)?)
+        };
+        Some((TraceId::new(trace_id_hex.to_string()), span_id))
+    }
+}
+
+impl Propagator for B3Propagator {
+    fn inject(&self, (trace_id, span_id): (TraceId, SpanId), carrier: &mut HashMap<String, String>) {
+        let trace_id_hex = trace_id_to_hex(&trace_id);
+        let span_id_hex = span_id_to_hex(&span_id);
+
+        carrier.insert(
+            B3_SINGLE_HEADER.to_string(),
+            format!("{}-{}-1", trace_id_hex, span_id_hex),
+        );
+        carrier.insert(B3_TRACE_ID_HEADER.to_string(), trace_id_hex);
+        carrier.insert(B3_SPAN_ID_HEADER.to_string(), span_id_hex);
+        carrier.insert(B3_SAMPLED_HEADER.to_string(), "1".to_string());
+    }
+
+    fn extract(&self, carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        Self::extract_single(carrier).or_else(|| Self::extract_multi(carrier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> (TraceId, SpanId) {
+        (
+            TraceId::new("some-opaque-trace-id".to_string()),
+            SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            },
+        )
+    }
+
+    #[test]
+    fn round_trips_single_header() {
+        let propagator = B3Propagator::new();
+        let mut carrier = HashMap::new();
+        propagator.inject(ctx(), &mut carrier);
+
+        let mut single_only = HashMap::new();
+        single_only.insert(B3_SINGLE_HEADER.to_string(), carrier[B3_SINGLE_HEADER].clone());
+        let (_, span_id) = propagator.extract(&single_only).unwrap();
+        assert_eq!(
+            span_id,
+            Some(SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            })
+        );
+    }
+
+    #[test]
+    fn round_trips_multi_header() {
+        let propagator = B3Propagator::new();
+        let mut carrier = HashMap::new();
+        propagator.inject(ctx(), &mut carrier);
+        carrier.remove(B3_SINGLE_HEADER);
+
+        let (_, span_id) = propagator.extract(&carrier).unwrap();
+        assert_eq!(
+            span_id,
+            Some(SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_missing_headers() {
+        let propagator = B3Propagator::new();
+        assert!(propagator.extract(&HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn rejects_non_hex_span_id_instead_of_treating_it_as_absent() {
+        let propagator = B3Propagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            B3_TRACE_ID_HEADER.to_string(),
+            "abcdefabcdefabcdefabcdefabcdefab".to_string(),
+        );
+        carrier.insert(B3_SPAN_ID_HEADER.to_string(), "not-hex-at-all!!".to_string());
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn trace_id_only_header_is_accepted_with_no_span_id() {
+        let propagator = B3Propagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            B3_TRACE_ID_HEADER.to_string(),
+            "abcdefabcdefabcdefabcdefabcdefab".to_string(),
+        );
+        let (_, span_id) = propagator.extract(&carrier).unwrap();
+        assert_eq!(span_id, None);
+    }
+}
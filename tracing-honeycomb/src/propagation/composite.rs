@@ -0,0 +1,90 @@
+use crate::honeycomb::{SpanId, TraceId};
+use crate::propagation::Propagator;
+use std::collections::HashMap;
+
+/// Tries each configured `Propagator`'s `extract` in order until one succeeds,
+/// and `inject`s into all of them. Lets a service accept/emit more than one
+/// wire format (e.g. behind an AWS ALB that also forwards B3 from a mesh).
+pub struct CompositePropagator {
+    propagators: Vec<Box<dyn Propagator>>,
+}
+
+impl std::fmt::Debug for CompositePropagator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompositePropagator")
+            .field("propagators", &self.propagators.len())
+            .finish()
+    }
+}
+
+impl CompositePropagator {
+    /// Construct a `CompositePropagator` that tries `propagators` in order.
+    pub fn new(propagators: Vec<Box<dyn Propagator>>) -> Self {
+        CompositePropagator { propagators }
+    }
+}
+
+impl Propagator for CompositePropagator {
+    fn inject(&self, ctx: (TraceId, SpanId), carrier: &mut HashMap<String, String>) {
+        for propagator in &self.propagators {
+            propagator.inject(ctx.clone(), carrier);
+        }
+    }
+
+    fn extract(&self, carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        self.propagators
+            .iter()
+            .find_map(|propagator| propagator.extract(carrier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::propagation::{B3Propagator, W3CPropagator};
+
+    fn ctx() -> (TraceId, SpanId) {
+        (
+            TraceId::new("abcdefabcdefabcdefabcdefabcdefab".to_string()),
+            SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            },
+        )
+    }
+
+    #[test]
+    fn extracts_from_first_matching_propagator() {
+        let composite = CompositePropagator::new(vec![
+            Box::new(W3CPropagator::new()),
+            Box::new(B3Propagator::new()),
+        ]);
+
+        // only a b3 header is present; w3c's extract should fail, b3's should succeed
+        let mut carrier = HashMap::new();
+        B3Propagator::new().inject(ctx(), &mut carrier);
+        carrier.remove("traceparent");
+
+        assert!(composite.extract(&carrier).is_some());
+    }
+
+    #[test]
+    fn injects_into_every_propagator() {
+        let composite = CompositePropagator::new(vec![
+            Box::new(W3CPropagator::new()),
+            Box::new(B3Propagator::new()),
+        ]);
+
+        let mut carrier = HashMap::new();
+        composite.inject(ctx(), &mut carrier);
+
+        assert!(carrier.contains_key("traceparent"));
+        assert!(carrier.contains_key("b3"));
+    }
+
+    #[test]
+    fn none_when_nothing_matches() {
+        let composite = CompositePropagator::new(vec![Box::new(W3CPropagator::new())]);
+        assert!(composite.extract(&HashMap::new()).is_none());
+    }
+}
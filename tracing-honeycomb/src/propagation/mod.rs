@@ -0,0 +1,33 @@
+//! Interop between honeycomb's own `TraceId`/`SpanId` and standard wire-format
+//! trace context headers, so a service can participate in someone else's trace.
+//!
+//! Each wire format (W3C, X-Ray, B3, ...) gets its own `Propagator` impl; use
+//! `CompositePropagator` to accept/emit more than one format at an edge.
+
+mod b3;
+mod composite;
+mod util;
+mod w3c;
+mod xray;
+
+pub use crate::propagation::b3::B3Propagator;
+pub use crate::propagation::composite::CompositePropagator;
+pub use crate::propagation::w3c::W3CPropagator;
+pub use crate::propagation::xray::XRayPropagator;
+
+use crate::honeycomb::{SpanId, TraceId};
+use std::collections::HashMap;
+
+/// Converts between honeycomb `(TraceId, SpanId)` pairs and a wire-format
+/// trace context carried in a header map.
+pub trait Propagator: Send + Sync {
+    /// Render `ctx` into `carrier`, overwriting any existing headers this
+    /// propagator owns.
+    fn inject(&self, ctx: (TraceId, SpanId), carrier: &mut HashMap<String, String>);
+
+    /// Recover a trace id (and, if present, a parent span id) from `carrier`.
+    ///
+    /// Returns `None` if `carrier` doesn't contain a valid context for this
+    /// propagator's format.
+    fn extract(&self, carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)>;
+}
@@ -0,0 +1,53 @@
+//! Shared id<->hex helpers used across wire-format propagators.
+
+use crate::honeycomb::{SpanId, TraceId};
+
+/// Fold a `TraceId` (which wraps an arbitrary string) into 32 lowercase hex
+/// chars, so ids of any shape/length fit a fixed-width wire field. The fold
+/// is a hash: extracting the field yields this hex, not the original id.
+pub(crate) fn trace_id_to_hex(trace_id: &TraceId) -> String {
+    format!("{:032x}", hash_to_u128(trace_id.to_string().as_bytes()))
+}
+
+fn hash_to_u128(bytes: &[u8]) -> u128 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut lo_hasher = DefaultHasher::new();
+    bytes.hash(&mut lo_hasher);
+    let lo = lo_hasher.finish() as u128;
+
+    let mut hi_hasher = DefaultHasher::new();
+    bytes.hash(&mut hi_hasher);
+    0u8.hash(&mut hi_hasher); // perturb so hi != lo
+    let hi = hi_hasher.finish() as u128;
+
+    (hi << 64) | lo
+}
+
+/// Render a `SpanId` as 16 lowercase hex chars (8 for `instance_id`, 8 for
+/// `tracing_id`).
+pub(crate) fn span_id_to_hex(span_id: &SpanId) -> String {
+    format!(
+        "{:08x}{:08x}",
+        span_id.instance_id as u32, span_id.tracing_id as u32
+    )
+}
+
+/// Parse a 16-hex-char span id back into a `SpanId`. Returns `None` if `hex`
+/// isn't exactly 16 hex chars.
+pub(crate) fn span_id_from_hex(hex: &str) -> Option<SpanId> {
+    if hex.len() != 16 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let instance_id = u64::from(u32::from_str_radix(&hex[0..8], 16).ok()?);
+    let tracing_id = u64::from(u32::from_str_radix(&hex[8..16], 16).ok()?);
+    Some(SpanId {
+        instance_id,
+        tracing_id,
+    })
+}
+
+pub(crate) fn is_all_zero_hex(s: &str) -> bool {
+    s.chars().all(|c| c == '0')
+}
@@ -0,0 +1,145 @@
+use crate::honeycomb::{SpanId, TraceId};
+use crate::propagation::util::{is_all_zero_hex, span_id_from_hex, span_id_to_hex, trace_id_to_hex};
+use crate::propagation::Propagator;
+use std::collections::HashMap;
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Propagates trace context using the W3C Trace Context `traceparent` header:
+/// `{version}-{trace_id}-{span_id}-{flags}`, where `version` is `00`, `trace_id`
+/// is 32 lowercase hex chars, `span_id` is 16 hex chars, and `flags` is 2 hex
+/// chars (bit 0 = sampled).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct W3CPropagator;
+
+impl W3CPropagator {
+    /// Construct a new `W3CPropagator`.
+    pub fn new() -> Self {
+        W3CPropagator
+    }
+}
+
+impl Propagator for W3CPropagator {
+    fn inject(&self, (trace_id, span_id): (TraceId, SpanId), carrier: &mut HashMap<String, String>) {
+        let header = format!(
+            "00-{}-{}-01",
+            trace_id_to_hex(&trace_id),
+            span_id_to_hex(&span_id)
+        );
+        carrier.insert(TRACEPARENT_HEADER.to_string(), header);
+    }
+
+    fn extract(&self, carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        let header = carrier.get(TRACEPARENT_HEADER)?;
+        let parts: Vec<&str> = header.split('-').collect();
+        if parts.len() != 4 {
+            return None;
+        }
+        let (version, trace_id_hex, span_id_hex, flags) = (parts[0], parts[1], parts[2], parts[3]);
+
+        if version.len() != 2 || trace_id_hex.len() != 32 || span_id_hex.len() != 16 || flags.len() != 2
+        {
+            return None;
+        }
+        if version != "00" {
+            return None;
+        }
+        if !trace_id_hex.chars().all(|c| c.is_ascii_hexdigit())
+            || !span_id_hex.chars().all(|c| c.is_ascii_hexdigit())
+            || !flags.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return None;
+        }
+        if is_all_zero_hex(trace_id_hex) || is_all_zero_hex(span_id_hex) {
+            return None;
+        }
+
+        Some((TraceId::new(trace_id_hex.to_string()), span_id_from_hex(span_id_hex)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> (TraceId, SpanId) {
+        (
+            TraceId::new("abcdefabcdefabcdefabcdefabcdefab".to_string()),
+            SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            },
+        )
+    }
+
+    #[test]
+    fn round_trips_through_inject_and_extract() {
+        let propagator = W3CPropagator::new();
+        let mut carrier = HashMap::new();
+        propagator.inject(ctx(), &mut carrier);
+
+        let (trace_id, span_id) = propagator.extract(&carrier).unwrap();
+        assert_eq!(
+            span_id,
+            Some(SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            })
+        );
+        assert_eq!(trace_id.to_string(), trace_id_to_hex(&ctx().0));
+    }
+
+    #[test]
+    fn rejects_malformed_header() {
+        let propagator = W3CPropagator::new();
+        let mut carrier = HashMap::new();
+
+        carrier.insert(TRACEPARENT_HEADER.to_string(), "not-a-traceparent".to_string());
+        assert!(propagator.extract(&carrier).is_none());
+
+        carrier.insert(
+            TRACEPARENT_HEADER.to_string(),
+            "00-tooshort-0123456789abcdef-01".to_string(),
+        );
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let propagator = W3CPropagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            TRACEPARENT_HEADER.to_string(),
+            format!("ff-{}-{}-01", "ab".repeat(16), "cd".repeat(8)),
+        );
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn rejects_non_hex_span_id() {
+        let propagator = W3CPropagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            TRACEPARENT_HEADER.to_string(),
+            format!("00-{}-{}-01", "ab".repeat(16), "nothexchars12345"),
+        );
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn rejects_all_zero_ids() {
+        let propagator = W3CPropagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            TRACEPARENT_HEADER.to_string(),
+            format!("00-{}-{}-01", "0".repeat(32), "0".repeat(16)),
+        );
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn missing_header_yields_none() {
+        let propagator = W3CPropagator::new();
+        assert!(propagator.extract(&HashMap::new()).is_none());
+    }
+}
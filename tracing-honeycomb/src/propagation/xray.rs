@@ -0,0 +1,143 @@
+use crate::honeycomb::{SpanId, TraceId};
+use crate::propagation::util::{is_all_zero_hex, span_id_from_hex, span_id_to_hex, trace_id_to_hex};
+use crate::propagation::Propagator;
+use std::collections::HashMap;
+
+const XRAY_HEADER: &str = "X-Amzn-Trace-Id";
+
+/// Propagates trace context using AWS X-Ray's `X-Amzn-Trace-Id` header:
+/// `Root=1-{8hex}-{24hex};Parent={16hex};Sampled={0|1}`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct XRayPropagator;
+
+impl XRayPropagator {
+    /// Construct a new `XRayPropagator`.
+    pub fn new() -> Self {
+        XRayPropagator
+    }
+}
+
+impl Propagator for XRayPropagator {
+    fn inject(&self, (trace_id, span_id): (TraceId, SpanId), carrier: &mut HashMap<String, String>) {
+        let hex = trace_id_to_hex(&trace_id);
+        let (epoch, unique) = hex.split_at(8);
+        let header = format!(
+            "Root=1-{}-{};Parent={};Sampled=1",
+            epoch,
+            unique,
+            span_id_to_hex(&span_id)
+        );
+        carrier.insert(XRAY_HEADER.to_string(), header);
+    }
+
+    fn extract(&self, carrier: &HashMap<String, String>) -> Option<(TraceId, Option<SpanId>)> {
+        let header = carrier.get(XRAY_HEADER)?;
+
+        let mut root = None;
+        let mut parent = None;
+        for field in header.split(';') {
+            let mut kv = field.splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some("Root"), Some(v)) => root = Some(v),
+                (Some("Parent"), Some(v)) => parent = Some(v),
+                _ => {}
+            }
+        }
+
+        let root = root?;
+        let root_parts: Vec<&str> = root.splitn(3, '-').collect();
+        if root_parts.len() != 3 || root_parts[0] != "1" {
+            return None;
+        }
+        let (epoch, unique) = (root_parts[1], root_parts[2]);
+        if epoch.len() != 8 || unique.len() != 24 {
+            return None;
+        }
+        if !epoch.chars().all(|c| c.is_ascii_hexdigit()) || !unique.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return None;
+        }
+        let trace_id_hex = format!("{}{}", epoch, unique);
+        if is_all_zero_hex(&trace_id_hex) {
+            return None;
+        }
+
+        // an absent `Parent` field means "trace id only", but a
+        // present-and-malformed one means the header itself is malformed,
+        // not parentless
+        let span_id = match parent {
+            None => None,
+            Some(v) => Some(span_id_from_hex(v)?),
+        };
+        Some((TraceId::new(trace_id_hex), span_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> (TraceId, SpanId) {
+        (
+            TraceId::new("some-opaque-trace-id".to_string()),
+            SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            },
+        )
+    }
+
+    #[test]
+    fn round_trips_through_inject_and_extract() {
+        let propagator = XRayPropagator::new();
+        let mut carrier = HashMap::new();
+        propagator.inject(ctx(), &mut carrier);
+
+        assert!(carrier[XRAY_HEADER].starts_with("Root=1-"));
+        let (_, span_id) = propagator.extract(&carrier).unwrap();
+        assert_eq!(
+            span_id,
+            Some(SpanId {
+                instance_id: 0x1111_2222,
+                tracing_id: 0x3333_4444,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_missing_or_malformed_header() {
+        let propagator = XRayPropagator::new();
+        assert!(propagator.extract(&HashMap::new()).is_none());
+
+        let mut carrier = HashMap::new();
+        carrier.insert(XRAY_HEADER.to_string(), "Root=garbage".to_string());
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn rejects_non_hex_parent_instead_of_treating_it_as_absent() {
+        let propagator = XRayPropagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            XRAY_HEADER.to_string(),
+            format!(
+                "Root=1-{}-{};Parent=not-a-valid-span-id;Sampled=1",
+                "a".repeat(8),
+                "b".repeat(24)
+            ),
+        );
+        assert!(propagator.extract(&carrier).is_none());
+    }
+
+    #[test]
+    fn root_only_header_is_accepted_with_no_parent() {
+        let propagator = XRayPropagator::new();
+        let mut carrier = HashMap::new();
+        carrier.insert(
+            XRAY_HEADER.to_string(),
+            format!("Root=1-{}-{}", "a".repeat(8), "b".repeat(24)),
+        );
+        let (_, span_id) = propagator.extract(&carrier).unwrap();
+        assert_eq!(span_id, None);
+    }
+}
@@ -0,0 +1,182 @@
+//! `tracing-subscriber`-style directive-based sampling, so sampling can be
+//! tuned per target/level instead of via one global rate.
+
+use crate::honeycomb::{Sampler, TraceId};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use tracing::Level;
+
+/// What to do with a span/event matched by a directive.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Decision {
+    /// Drop it.
+    Off,
+    /// Always keep it.
+    Always,
+    /// Keep it if `hash(trace_id) % n < k`, so every span/event in a trace
+    /// shares one decision.
+    Rate { k: u32, n: u32 },
+}
+
+impl Decision {
+    fn evaluate(&self, trace_id: &TraceId) -> bool {
+        match *self {
+            Decision::Off => false,
+            Decision::Always => true,
+            Decision::Rate { k, n } => {
+                if n == 0 {
+                    return false;
+                }
+                (hash_trace_id(trace_id) % u64::from(n)) < u64::from(k)
+            }
+        }
+    }
+}
+
+impl FromStr for Decision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Decision::Off),
+            "always" => Ok(Decision::Always),
+            _ => {
+                let mut parts = s.splitn(2, '/');
+                let k = parts.next().ok_or_else(|| format!("invalid directive: {}", s))?;
+                let n = parts.next().ok_or_else(|| format!("invalid directive: {}", s))?;
+                let k: u32 = k.parse().map_err(|_| format!("invalid rate: {}", s))?;
+                let n: u32 = n.parse().map_err(|_| format!("invalid rate: {}", s))?;
+                Ok(Decision::Rate { k, n })
+            }
+        }
+    }
+}
+
+fn hash_trace_id(trace_id: &TraceId) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    trace_id.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A `Sampler` that parses `tracing-subscriber`-style per-target/level
+/// directives, e.g. `"mycrate::db=always,warn=always,info=1/50,trace=off"`.
+///
+/// Directives are matched most-specific-target-first; a directive naming a
+/// level applies only to spans/events at exactly that level, not a threshold,
+/// so configured order never changes which directive wins. Targets and levels
+/// that match no directive are kept.
+pub struct DirectiveSampler {
+    // sorted longest-target-first, so the most specific match wins regardless
+    // of configured order
+    targets: Vec<(String, Decision)>,
+    levels: HashMap<Level, Decision>,
+}
+
+impl std::fmt::Debug for DirectiveSampler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectiveSampler")
+            .field("targets", &self.targets)
+            .field("levels", &self.levels)
+            .finish()
+    }
+}
+
+impl DirectiveSampler {
+    /// Parse a comma-separated directive string. Unparseable directives are
+    /// skipped.
+    pub fn new(directives: &str) -> Self {
+        let mut targets = Vec::new();
+        let mut levels = HashMap::new();
+
+        for directive in directives.split(',') {
+            let directive = directive.trim();
+            if directive.is_empty() {
+                continue;
+            }
+            let mut parts = directive.splitn(2, '=');
+            let (key, decision) = match (parts.next(), parts.next()) {
+                (Some(key), Some(decision)) => (key, decision),
+                _ => continue,
+            };
+            let decision = match Decision::from_str(decision) {
+                Ok(decision) => decision,
+                Err(_) => continue,
+            };
+
+            if let Ok(level) = Level::from_str(key) {
+                levels.insert(level, decision);
+            } else {
+                targets.push((key.to_string(), decision));
+            }
+        }
+
+        targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+
+        DirectiveSampler { targets, levels }
+    }
+}
+
+impl Sampler for DirectiveSampler {
+    fn sample(&self, target: &str, level: &Level, trace_id: &TraceId) -> bool {
+        for (directive_target, decision) in &self.targets {
+            if target.starts_with(directive_target.as_str()) {
+                return decision.evaluate(trace_id);
+            }
+        }
+
+        if let Some(decision) = self.levels.get(level) {
+            return decision.evaluate(trace_id);
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace_id() -> TraceId {
+        TraceId::new("some-trace-id".to_string())
+    }
+
+    #[test]
+    fn exact_level_match_is_order_independent() {
+        // from the feature request itself
+        let forward = DirectiveSampler::new("mycrate::db=always,warn=always,info=1/50,trace=off");
+        let reordered = DirectiveSampler::new("trace=off,warn=always");
+
+        assert!(forward.sample("other::target", &Level::WARN, &trace_id()));
+        assert!(reordered.sample("other::target", &Level::WARN, &trace_id()));
+    }
+
+    #[test]
+    fn unmatched_level_is_kept_by_default() {
+        let sampler = DirectiveSampler::new("mycrate::db=always,warn=always,info=1/50,trace=off");
+        // nothing names debug explicitly, so it isn't caught by trace=off
+        assert!(sampler.sample("other::target", &Level::DEBUG, &trace_id()));
+    }
+
+    #[test]
+    fn target_directive_takes_priority_over_level() {
+        let sampler = DirectiveSampler::new("mycrate::db=always,warn=off");
+        assert!(sampler.sample("mycrate::db::query", &Level::WARN, &trace_id()));
+    }
+
+    #[test]
+    fn most_specific_target_wins() {
+        let sampler = DirectiveSampler::new("mycrate=off,mycrate::db=always");
+        assert!(sampler.sample("mycrate::db::query", &Level::INFO, &trace_id()));
+        assert!(!sampler.sample("mycrate::http", &Level::INFO, &trace_id()));
+    }
+
+    #[test]
+    fn rate_decision_is_deterministic_per_trace_id() {
+        let sampler = DirectiveSampler::new("info=1/2");
+        let a = sampler.sample("x", &Level::INFO, &trace_id());
+        let b = sampler.sample("x", &Level::INFO, &trace_id());
+        assert_eq!(a, b);
+    }
+}
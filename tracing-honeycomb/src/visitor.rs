@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use tracing::field::{Field, Visit};
+
+/// `tracing::field::Visit` implementation that buffers field values into a map,
+/// keyed by field name, suitable for attaching to a honeycomb event.
+///
+/// Field names that collide with honeycomb's own reserved field names (e.g.
+/// `duration_ms`) are renamed with a `tracing.` prefix to avoid clobbering them.
+pub struct HoneycombVisitor(pub HashMap<String, libhoney::Value>);
+
+const RESERVED_FIELD_NAMES: &[&str] = &["duration_ms"];
+
+fn resolve_field_name(name: &str) -> String {
+    if RESERVED_FIELD_NAMES.contains(&name) {
+        format!("tracing.{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+impl Visit for HoneycombVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0
+            .insert(resolve_field_name(field.name()), libhoney::json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0
+            .insert(resolve_field_name(field.name()), libhoney::json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0
+            .insert(resolve_field_name(field.name()), libhoney::json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .insert(resolve_field_name(field.name()), libhoney::json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(
+            resolve_field_name(field.name()),
+            libhoney::json!(format!("{:?}", value)),
+        );
+    }
+}